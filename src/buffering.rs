@@ -1,9 +1,9 @@
 use std::{
-    fs::{File, OpenOptions},
-    io::{Read, Seek, SeekFrom, Write},
+    io::SeekFrom,
     path::{Path, PathBuf},
     pin::Pin,
-    task::{Context, Poll},
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
 };
 
 use actix_web::{
@@ -11,7 +11,11 @@ use actix_web::{
     web::{Bytes, BytesMut},
     HttpMessage,
 };
-use futures::{ready, Stream, StreamExt};
+use futures::{future::LocalBoxFuture, ready, FutureExt, Stream, StreamExt};
+#[cfg(unix)]
+use memmap2::Mmap;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use uuid::Uuid;
 
 struct RequestBufferedMark;
@@ -55,56 +59,137 @@ where
     }
 }
 
-// File buffering stream wrapper. After wrap stream can be read multiple times
-pub struct FileBufferingStreamWrapper {
+// Buffering knobs shared verbatim by every stream/reader produced from a
+// `FileBufferingStreamWrapper` - grouped into one struct so `wrap`/`wrap_shared`
+// can hand them off with a single cheap clone instead of threading each field
+// through their constructors separately.
+#[derive(Clone)]
+struct BufferingOptions {
     tmp_dir: PathBuf,
     threshold: usize,
     produce_chunk_size: usize,
     buffer_limit: Option<usize>,
+    content_addressed: bool,
+    mmap_reads: bool,
+    write_buf_size: usize,
+}
+
+// File buffering stream wrapper. After wrap stream can be read multiple times
+pub struct FileBufferingStreamWrapper {
+    options: BufferingOptions,
+}
+
+impl Default for FileBufferingStreamWrapper {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl FileBufferingStreamWrapper {
     pub fn new() -> Self {
         Self {
-            tmp_dir: std::env::temp_dir(),
-            threshold: 1024 * 30,
-            produce_chunk_size: 1024 * 30,
-            buffer_limit: None,
+            options: BufferingOptions {
+                tmp_dir: std::env::temp_dir(),
+                threshold: 1024 * 30,
+                produce_chunk_size: 1024 * 30,
+                buffer_limit: None,
+                content_addressed: false,
+                mmap_reads: false,
+                write_buf_size: 1024 * 8,
+            },
         }
     }
 
     // The temporary dir for larger bodies
     pub fn tmp_dir(mut self, v: impl AsRef<Path>) -> Self {
-        self.tmp_dir = v.as_ref().to_path_buf();
+        self.options.tmp_dir = v.as_ref().to_path_buf();
         self
     }
 
     // The maximum size in bytes of the in-memory used to buffer the stream. Larger bodies are written to disk
     pub fn threshold(mut self, v: usize) -> Self {
-        self.threshold = v;
+        self.options.threshold = v;
         self
     }
 
     // The chunk size for read buffered bodies
     pub fn produce_chunk_size(mut self, v: usize) -> Self {
-        self.produce_chunk_size = v;
+        self.options.produce_chunk_size = v;
         self
     }
 
     // The maximum size in bytes of the body. An attempt to read beyond this limit will cause an error
     pub fn buffer_limit(mut self, v: Option<usize>) -> Self {
-        self.buffer_limit = v;
+        self.options.buffer_limit = v;
+        self
+    }
+
+    // Spill bodies into a shared, content-addressed store keyed by digest, so
+    // identical uploads/responses reuse a single on-disk blob instead of each
+    // being spilled separately
+    pub fn content_addressed(mut self, v: bool) -> Self {
+        self.options.content_addressed = v;
+        self
+    }
+
+    // Serve the replay phase of a file-backed body from a memory map instead
+    // of seek+read syscalls per chunk, falling back to the copy-based path
+    // when a mapping can't be established (including on non-unix targets,
+    // where it's never available).
+    //
+    // This does not keep the mapping itself around: `Bytes` (the `bytes` 0.5
+    // release actix-web 3 pins) can't wrap an arbitrary owner like `Mmap`
+    // without copying, so the whole spilled file is copied into a `Bytes`
+    // once and that copy is cached for the buffer's lifetime - `Bytes::slice`
+    // on it is still zero-copy per chunk, trading one copy of the whole body
+    // for the many syscalls it replaces. That copy pins the entire body in
+    // memory for as long as the wrapper is kept around for repeat reads, so
+    // this can cost more memory than the copy-based path for large bodies
+    // that are replayed once and dropped.
+    pub fn mmap_reads(mut self, v: bool) -> Self {
+        self.options.mmap_reads = v;
+        self
+    }
+
+    // How many bytes to accumulate in memory before flushing staged chunks
+    // to a spilled file as a single vectored write
+    pub fn write_buf_size(mut self, v: usize) -> Self {
+        self.options.write_buf_size = v;
         self
     }
 
     pub fn wrap<S>(&self, inner: S) -> FileBufferingStream<S> {
-        FileBufferingStream::new(
-            inner,
-            self.tmp_dir.to_path_buf(),
-            self.threshold,
-            self.produce_chunk_size,
-            self.buffer_limit,
-        )
+        FileBufferingStream::new(inner, self.options.clone())
+    }
+
+    // Like `wrap`, but returns a shareable handle instead of a single-consumer
+    // stream. Call `.reader()` on the result as many times as needed to hand
+    // out independent, concurrently pollable replays of the same body - e.g.
+    // the request handler and an audit task reading it side by side.
+    //
+    // Returns an error if `content_addressed(true)` was set - dedup isn't
+    // wired up for shared buffers yet, so combining them would silently skip
+    // hashing.
+    pub fn wrap_shared<S>(&self, inner: S) -> Result<Arc<SharedFileBuffering<S>>, BufferingError> {
+        if self.options.content_addressed {
+            return Err(BufferingError::UnsupportedCombination(
+                "content_addressed(true) is not supported together with wrap_shared: \
+                 BufferReader never drives FileBuffer::start_finalize, so a shared \
+                 buffer would never hash into the content store or release its blob",
+            ));
+        }
+
+        Ok(Arc::new(SharedFileBuffering {
+            options: self.options.clone(),
+            core: Mutex::new(SharedCore {
+                inner,
+                buffer: Buffer::Memory(BytesMut::new()),
+                buffer_size: 0,
+                inner_eof: false,
+                failed: false,
+            }),
+            wakers: Mutex::new(Vec::new()),
+        }))
     }
 }
 
@@ -114,53 +199,639 @@ impl AsRef<FileBufferingStreamWrapper> for FileBufferingStreamWrapper {
     }
 }
 
+// The file type backing a spilled buffer. `open_rw`/`backend_*` below are the
+// only things in this module that touch it directly, so swapping the backend
+// later only means changing this handful of functions.
+//
+// An earlier revision of this module could swap `BackendFile` for a
+// completion-based backend on top of `tokio-uring` behind an `io-uring`
+// feature flag. That backend's ops can only run inside a dedicated
+// `tokio_uring::start(...)` context with its driver installed - actix-web
+// runs handlers on a plain multi-threaded actix-rt/tokio runtime with no
+// io_uring driver, so every spill under that feature panicked on the first
+// real disk write. Bridging the two properly needs a dedicated uring thread
+// driven over a channel, which is a bigger piece of work than fits here, so
+// the feature was pulled rather than ship a backend that's broken by
+// construction for the one runtime this crate actually targets.
+type BackendFile = tokio::fs::File;
+
+async fn open_rw(path: &Path) -> std::io::Result<BackendFile> {
+    tokio::fs::OpenOptions::new()
+        .write(true)
+        .read(true)
+        .create_new(true)
+        .open(path)
+        .await
+}
+
+// Plain `&[u8]` write rather than `AsyncWriteExt::write_all_buf`: `Bytes`
+// here is `actix_web::web::Bytes` (bytes 0.5, pinned by actix-web 3), which
+// doesn't implement the `bytes` 1.x `Buf` trait tokio 1.x's write_all_buf
+// requires - `Bytes` derefs to `&[u8]` regardless of which bytes major
+// version produced it, so writing through that avoids the mismatch.
+async fn backend_write_all(file: &mut BackendFile, buf: Bytes) -> std::io::Result<()> {
+    file.write_all(&buf).await
+}
+
+// Flushes several staged chunks in as few syscalls as possible via vectored
+// writes, advancing each `Bytes` past whatever a partial write consumed
+// instead of concatenating them first.
+async fn backend_write_vectored(file: &mut BackendFile, mut chunks: Vec<Bytes>) -> std::io::Result<()> {
+    chunks.retain(|b| !b.is_empty());
+
+    while !chunks.is_empty() {
+        let slices: Vec<std::io::IoSlice> = chunks.iter().map(|b| std::io::IoSlice::new(b)).collect();
+        let mut written = file.write_vectored(&slices).await?;
+        drop(slices);
+
+        if written == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::WriteZero,
+                "failed to write whole buffer",
+            ));
+        }
+
+        while written > 0 {
+            if chunks[0].len() <= written {
+                written -= chunks[0].len();
+                chunks.remove(0);
+            } else {
+                // `Bytes::slice` (not the `bytes` 1.x-only `Buf::advance`)
+                // so this keeps working against the 0.5 `Bytes` actix-web 3
+                // hands us.
+                chunks[0] = chunks[0].slice(written..);
+                written = 0;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn backend_read_exact(file: &mut BackendFile, len: usize) -> std::io::Result<Vec<u8>> {
+    let mut buf = vec![0u8; len];
+    file.read_exact(&mut buf).await?;
+    Ok(buf)
+}
+
+async fn open_rw_existing(path: &Path) -> std::io::Result<BackendFile> {
+    tokio::fs::OpenOptions::new()
+        .write(true)
+        .read(true)
+        .open(path)
+        .await
+}
+
+async fn backend_seek(file: &mut BackendFile, pos: u64) -> std::io::Result<()> {
+    file.seek(SeekFrom::Start(pos)).await.map(|_| ())
+}
+
+// Maps the whole spilled file into memory once and copies it into a `Bytes`
+// so `read_from_buffer` can hand out slices of that one buffer during replay
+// instead of a seek+read syscall per chunk. `Bytes` here is
+// `actix_web::web::Bytes` (bytes 0.5, pinned by actix-web 3), which has no
+// way to wrap an arbitrary owner like `Mmap` without copying - `Bytes::slice`
+// on the copy is still zero-copy per chunk, so this trades one copy of the
+// whole body for the many syscalls it replaces.
+#[cfg(unix)]
+fn mmap_file(file: &BackendFile) -> std::io::Result<Bytes> {
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+
+    // `Mmap::map` wants a `std::fs::File`; borrow the backing fd without
+    // taking ownership of it so closing this temporary doesn't close `file`.
+    let std_file = unsafe { std::fs::File::from_raw_fd(file.as_raw_fd()) };
+    let mapped = unsafe { Mmap::map(&std_file) };
+    std::mem::forget(std_file);
+
+    Ok(Bytes::copy_from_slice(&mapped?))
+}
+
+#[cfg(not(unix))]
+fn mmap_file(_file: &BackendFile) -> std::io::Result<Bytes> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "mmap reads are not supported on this backend",
+    ))
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+// A shared, content-addressed store for spilled bodies: two bodies with the
+// same bytes hash to the same digest and end up sharing one on-disk blob. A
+// fixed sidecar header makes every blob self-describing, and an in-memory
+// refcount table means a blob is only unlinked once the last
+// `FileBufferingStream` referencing it drops.
+mod content_store {
+    use std::{
+        collections::HashMap,
+        convert::TryInto,
+        path::{Path, PathBuf},
+        sync::{Mutex, OnceLock},
+    };
+
+    use sha2::{Digest, Sha256};
+    use uuid::Uuid;
+
+    const MAGIC: &[u8; 4] = b"AWBF";
+    pub const HEADER_LEN: usize = 4 + 16 + 8 + 8 + 32;
+
+    // Sidecar header written ahead of every stored blob: magic, uuid, ctime,
+    // content length and the 32-byte digest, in that order.
+    pub struct BlobHeader {
+        pub uuid: Uuid,
+        pub ctime: u64,
+        pub content_length: u64,
+        pub digest: [u8; 32],
+    }
+
+    impl BlobHeader {
+        pub fn encode(&self) -> Vec<u8> {
+            let mut buf = Vec::with_capacity(HEADER_LEN);
+            buf.extend_from_slice(MAGIC);
+            buf.extend_from_slice(self.uuid.as_bytes());
+            buf.extend_from_slice(&self.ctime.to_le_bytes());
+            buf.extend_from_slice(&self.content_length.to_le_bytes());
+            buf.extend_from_slice(&self.digest);
+            buf
+        }
+
+        pub fn decode(buf: &[u8]) -> std::io::Result<Self> {
+            if buf.len() < HEADER_LEN || &buf[0..4] != MAGIC {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "not a content-addressed buffering blob",
+                ));
+            }
+
+            let uuid = Uuid::from_slice(&buf[4..20])
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            let ctime = u64::from_le_bytes(buf[20..28].try_into().unwrap());
+            let content_length = u64::from_le_bytes(buf[28..36].try_into().unwrap());
+            let mut digest = [0u8; 32];
+            digest.copy_from_slice(&buf[36..68]);
+
+            Ok(Self {
+                uuid,
+                ctime,
+                content_length,
+                digest,
+            })
+        }
+    }
+
+    pub fn digest_path(dir: &Path, digest: &[u8; 32]) -> PathBuf {
+        let mut hex = String::with_capacity(digest.len() * 2);
+        for b in digest {
+            hex.push_str(&format!("{:02x}", b));
+        }
+        dir.join(hex)
+    }
+
+    // `pub(crate)` rather than private so tests can assert on the refcount
+    // directly instead of only observing it through acquire/release.
+    pub(crate) fn refs() -> &'static Mutex<HashMap<PathBuf, usize>> {
+        static REFS: OnceLock<Mutex<HashMap<PathBuf, usize>>> = OnceLock::new();
+        REFS.get_or_init(|| Mutex::new(HashMap::new()))
+    }
+
+    // Registers a live reference to the blob at `path`, whether it was just
+    // created by this caller or already existed.
+    pub fn acquire(path: &Path) {
+        let mut refs = refs().lock().unwrap();
+        *refs.entry(path.to_path_buf()).or_insert(0) += 1;
+    }
+
+    // Drops a reference; returns `true` only when the caller held the last
+    // one, meaning the blob is now safe to unlink.
+    pub fn release(path: &Path) -> bool {
+        let mut refs = refs().lock().unwrap();
+        match refs.get_mut(path) {
+            Some(count) if *count > 1 => {
+                *count -= 1;
+                false
+            }
+            Some(_) => {
+                refs.remove(path);
+                true
+            }
+            None => true,
+        }
+    }
+
+    // Re-hashes a stored blob against the digest recorded in its own header.
+    pub async fn verify(path: &Path) -> std::io::Result<bool> {
+        use tokio::io::AsyncReadExt;
+
+        let mut file = tokio::fs::File::open(path).await?;
+
+        let mut header_buf = vec![0u8; HEADER_LEN];
+        file.read_exact(&mut header_buf).await?;
+        let header = BlobHeader::decode(&header_buf)?;
+
+        let mut hasher = Sha256::new();
+        let mut chunk = vec![0u8; 64 * 1024];
+        loop {
+            let n = file.read(&mut chunk).await?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&chunk[..n]);
+        }
+
+        let digest: [u8; 32] = hasher.finalize().into();
+        Ok(digest == header.digest)
+    }
+}
+
+// Re-hashes a spilled, content-addressed blob and checks it against the
+// digest recorded in its sidecar header.
+pub async fn verify_content_addressed_blob(path: impl AsRef<Path>) -> std::io::Result<bool> {
+    content_store::verify(path.as_ref()).await
+}
+
+// A pending read against the spilled file: the reopened/reused handle plus
+// however many bytes were asked for, handed back once the read lands.
+type ReadFuture = LocalBoxFuture<'static, std::io::Result<(BackendFile, Vec<u8>)>>;
+
+// An in-flight async operation against the spilled file. `poll_next` drives
+// whichever one is pending instead of ever touching the file synchronously.
+enum FileOp {
+    Idle,
+    Writing(LocalBoxFuture<'static, std::io::Result<BackendFile>>),
+    Reading(ReadFuture),
+}
+
+// What finished when `FileBuffer::poll_op` resolves.
+enum FileOpOutcome {
+    // A staged flush landed on disk; it carries no bytes of its own since
+    // `write_to_buffer` already handed its chunk back to the caller when it
+    // was staged.
+    Flushed,
+    Read(Bytes),
+}
+
+struct FileBuffer {
+    path: PathBuf,
+    // `None` while an operation owns the file; `Some` when idle (including
+    // before the file has ever been opened).
+    file: Option<BackendFile>,
+    op: FileOp,
+
+    // Incoming chunks accumulate here instead of hitting disk one at a time;
+    // `maybe_flush` drains them as a single vectored write once `staged_len`
+    // crosses `write_buf_size`.
+    write_buf_size: usize,
+    staging: Vec<Bytes>,
+    staged_len: usize,
+
+    // Content-addressed dedup state; only populated when the wrapper was
+    // built with `content_addressed(true)`.
+    content_addressed: bool,
+    blob_uuid: Uuid,
+    created_at: u64,
+    body_offset: u64,
+    hasher: Option<Sha256>,
+    finalize_op: Option<LocalBoxFuture<'static, std::io::Result<(BackendFile, PathBuf)>>>,
+    finalized: bool,
+
+    // How many bytes are actually durable on disk, and how many are in the
+    // flush currently in flight. A `SharedFileBuffering` reader may only
+    // read back bytes counted in `durable_len` - the writer's own replay
+    // path (`FileBufferingStream`) never needs this, since it always drains
+    // any in-flight flush before it starts reading.
+    durable_len: u64,
+    in_flight_len: u64,
+
+    // Lazily established the first time the replay phase reads this buffer,
+    // once the file's length is stable. `None` means reads still go through
+    // the copy-based path (`FileOp::Reading`, or a `BufferReader`'s own
+    // `read_op`).
+    mmap: Option<Bytes>,
+
+    // Set once a write or read against `self.path` has errored. `self.file`
+    // is gone for good at that point - the future that owned it was dropped
+    // along with the error - so there's no handle left to hand to a later
+    // `start_read`/`start_finalize`. Mirrors `SharedCore::failed`: once set,
+    // every later operation on this buffer errors instead of retrying.
+    failed: bool,
+}
+
+impl FileBuffer {
+    fn new(path: PathBuf, write_buf_size: usize, content_addressed: bool) -> Self {
+        let body_offset = if content_addressed {
+            content_store::HEADER_LEN as u64
+        } else {
+            0
+        };
+
+        Self {
+            path,
+            file: None,
+            op: FileOp::Idle,
+            write_buf_size,
+            staging: Vec::new(),
+            staged_len: 0,
+            content_addressed,
+            blob_uuid: Uuid::new_v4(),
+            created_at: now_secs(),
+            body_offset,
+            hasher: if content_addressed {
+                Some(Sha256::new())
+            } else {
+                None
+            },
+            finalize_op: None,
+            finalized: false,
+            durable_len: 0,
+            in_flight_len: 0,
+            mmap: None,
+            failed: false,
+        }
+    }
+
+    // Fails fast once a previous operation has already lost the file handle,
+    // instead of `start_read`/`start_finalize` panicking on a missing `file`.
+    fn check_failed(&self) -> Result<(), BufferingError> {
+        if self.failed {
+            return Err(BufferingError::Io(std::io::Error::other(
+                "buffered file is unusable after a previous I/O error",
+            )));
+        }
+        Ok(())
+    }
+
+    // Queues `bytes` for the next flush. Cheap and synchronous - no disk I/O
+    // happens here, so the caller can keep pulling from the inner stream.
+    fn stage(&mut self, bytes: Bytes) {
+        if let Some(hasher) = self.hasher.as_mut() {
+            hasher.update(&bytes);
+        }
+
+        self.staged_len += bytes.len();
+        self.staging.push(bytes);
+    }
+
+    // Starts a flush once enough has been staged. A flush already in flight
+    // is left alone; staging just keeps growing until it completes.
+    fn maybe_flush(&mut self) {
+        if matches!(self.op, FileOp::Idle)
+            && !self.staging.is_empty()
+            && self.staged_len >= self.write_buf_size
+        {
+            self.start_flush();
+        }
+    }
+
+    // Flushes whatever is staged regardless of size, used once the inner
+    // stream hits EOF so the replay phase never reads a stale tail.
+    fn flush_remaining(&mut self) {
+        if matches!(self.op, FileOp::Idle) && !self.staging.is_empty() {
+            self.start_flush();
+        }
+    }
+
+    fn start_flush(&mut self) {
+        let chunks = std::mem::take(&mut self.staging);
+        self.in_flight_len = self.staged_len as u64;
+        self.staged_len = 0;
+
+        let mut file = self.file.take();
+        let path = self.path.clone();
+        let content_addressed = self.content_addressed;
+
+        let fut = async move {
+            let mut file = match file.take() {
+                Some(file) => file,
+                None => {
+                    let mut file = open_rw(&path).await?;
+                    if content_addressed {
+                        let placeholder = Bytes::from(vec![0u8; content_store::HEADER_LEN]);
+                        backend_write_all(&mut file, placeholder).await?;
+                    }
+                    file
+                }
+            };
+
+            backend_write_vectored(&mut file, chunks).await?;
+            Ok(file)
+        }
+        .boxed_local();
+
+        self.op = FileOp::Writing(fut);
+    }
+
+    fn start_read(&mut self, len: usize, seek_to_start: bool) {
+        let mut file = self
+            .file
+            .take()
+            .expect("file buffer operation already in flight");
+        let body_offset = self.body_offset;
+
+        let fut = async move {
+            if seek_to_start {
+                backend_seek(&mut file, body_offset).await?;
+            }
+            let data = backend_read_exact(&mut file, len).await?;
+            Ok((file, data))
+        }
+        .boxed_local();
+
+        self.op = FileOp::Reading(fut);
+    }
+
+    // Finalizes the blob once the body is fully written: stamps the real
+    // sidecar header in place of the placeholder, then links the temp file
+    // into the shared store under its digest (or reuses an existing blob
+    // with the same digest, dropping the temp file).
+    fn start_finalize(&mut self, tmp_dir: &Path, content_length: u64) {
+        let file = self
+            .file
+            .take()
+            .expect("file buffer operation already in flight");
+        let hasher = self.hasher.take().expect("finalize without a hasher");
+        let tmp_path = self.path.clone();
+        let uuid = self.blob_uuid;
+        let ctime = self.created_at;
+        let tmp_dir = tmp_dir.to_path_buf();
+
+        let fut = async move {
+            let digest: [u8; 32] = hasher.finalize().into();
+            let header = content_store::BlobHeader {
+                uuid,
+                ctime,
+                content_length,
+                digest,
+            };
+
+            let mut file = file;
+            backend_seek(&mut file, 0).await?;
+            backend_write_all(&mut file, Bytes::from(header.encode())).await?;
+
+            let target = content_store::digest_path(&tmp_dir, &digest);
+            match tokio::fs::hard_link(&tmp_path, &target).await {
+                Ok(()) => {
+                    tokio::fs::remove_file(&tmp_path).await?;
+                    content_store::acquire(&target);
+                    Ok((file, target))
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    drop(file);
+                    tokio::fs::remove_file(&tmp_path).await?;
+                    let existing = open_rw_existing(&target).await?;
+                    content_store::acquire(&target);
+                    Ok((existing, target))
+                }
+                Err(e) => Err(e),
+            }
+        }
+        .boxed_local();
+
+        self.finalize_op = Some(fut);
+    }
+
+    fn poll_finalize(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), BufferingError>> {
+        let fut = self
+            .finalize_op
+            .as_mut()
+            .expect("poll_finalize called without an in-flight finalize");
+
+        match fut.as_mut().poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Err(e)) => {
+                self.finalize_op = None;
+                self.failed = true;
+                Poll::Ready(Err(e.into()))
+            }
+            Poll::Ready(Ok((file, path))) => {
+                self.file = Some(file);
+                self.path = path;
+                self.finalize_op = None;
+                self.finalized = true;
+                Poll::Ready(Ok(()))
+            }
+        }
+    }
+
+    // Drives whatever operation is in flight. Returns `None` when idle, so
+    // the caller knows it's free to start a new read or write.
+    fn poll_op(&mut self, cx: &mut Context<'_>) -> Option<Poll<Result<FileOpOutcome, BufferingError>>> {
+        match self.op {
+            FileOp::Idle => None,
+            FileOp::Writing(ref mut fut) => match fut.as_mut().poll(cx) {
+                Poll::Pending => Some(Poll::Pending),
+                Poll::Ready(Err(e)) => {
+                    self.op = FileOp::Idle;
+                    self.failed = true;
+                    Some(Poll::Ready(Err(e.into())))
+                }
+                Poll::Ready(Ok(file)) => {
+                    self.file = Some(file);
+                    self.op = FileOp::Idle;
+                    self.durable_len += self.in_flight_len;
+                    self.in_flight_len = 0;
+                    Some(Poll::Ready(Ok(FileOpOutcome::Flushed)))
+                }
+            },
+            FileOp::Reading(ref mut fut) => match fut.as_mut().poll(cx) {
+                Poll::Pending => Some(Poll::Pending),
+                Poll::Ready(Err(e)) => {
+                    self.op = FileOp::Idle;
+                    self.failed = true;
+                    Some(Poll::Ready(Err(e.into())))
+                }
+                Poll::Ready(Ok((file, data))) => {
+                    self.file = Some(file);
+                    self.op = FileOp::Idle;
+                    Some(Poll::Ready(Ok(FileOpOutcome::Read(Bytes::from(data)))))
+                }
+            },
+        }
+    }
+}
+
 enum Buffer {
     Memory(BytesMut),
-    File(PathBuf, File),
+    // Boxed: `FileBuffer` carries several in-flight-future and dedup fields
+    // and is far larger than the `BytesMut` in the `Memory` variant, so
+    // leaving it unboxed would bloat every `Buffer` to `FileBuffer`'s size
+    // even while buffering entirely in memory.
+    File(Box<FileBuffer>),
 }
 
 pub struct FileBufferingStream<S> {
     inner: S,
     inner_eof: bool,
 
-    tmp_dir: PathBuf,
-    threshold: usize,
-    produce_chunk_size: usize,
-    buffer_limit: Option<usize>,
+    options: BufferingOptions,
 
     buffer: Buffer,
     buffer_size: usize,
     produce_index: usize,
 }
 
+// Schedules async removal of a spilled file when the buffer holding it (or
+// the last reference to a shared, content-addressed blob) is dropped, so
+// teardown never blocks on disk I/O.
+fn schedule_buffer_cleanup(buffer: &Buffer) {
+    if let Buffer::File(fb) = buffer {
+        // `maybe_flush` only fires once `staged_len` crosses `write_buf_size`,
+        // so a buffer that never got there (e.g. `write_buf_size` configured
+        // larger than `threshold`, or the connection dropped mid-upload
+        // before the threshold was even crossed) has never actually opened
+        // `path` on disk. Removing it anyway just logs a bogus "No such file
+        // or directory" on every such drop.
+        if fb.file.is_none() && fb.durable_len == 0 && matches!(fb.op, FileOp::Idle) {
+            return;
+        }
+
+        let path = fb.path.clone();
+
+        // A finalized, content-addressed blob may still be referenced by
+        // other buffers sharing the same digest; only the last holder
+        // actually unlinks it.
+        let should_remove = if fb.content_addressed && fb.finalized {
+            content_store::release(&path)
+        } else {
+            true
+        };
+
+        if !should_remove {
+            return;
+        }
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = tokio::fs::remove_file(&path).await {
+                        println!("error at remove buffering file {:?}. {}", path, e);
+                    }
+                });
+            }
+            Err(e) => {
+                println!("leaking buffering file {:?}: no tokio runtime to schedule cleanup on. {}", path, e);
+            }
+        }
+    }
+}
+
 impl<S> Drop for FileBufferingStream<S> {
     fn drop(&mut self) {
-        match self.buffer {
-            Buffer::Memory(_) => {}
-            Buffer::File(ref path, _) => match std::fs::remove_file(path) {
-                Ok(_) => {}
-                Err(e) => println!("error at remove buffering file {:?}. {}", path, e),
-            },
-        };
+        schedule_buffer_cleanup(&self.buffer);
     }
 }
 
 impl<S> FileBufferingStream<S> {
-    fn new(
-        inner: S,
-        tmp_dir: PathBuf,
-        threshold: usize,
-        produce_chunk_size: usize,
-        buffer_limit: Option<usize>,
-    ) -> Self {
+    fn new(inner: S, options: BufferingOptions) -> Self {
         Self {
-            inner: inner,
+            inner,
             inner_eof: false,
 
-            tmp_dir,
-            threshold,
-            produce_chunk_size,
-            buffer_limit: buffer_limit,
+            options,
 
             buffer: Buffer::Memory(BytesMut::new()),
             buffer_size: 0,
@@ -168,87 +839,122 @@ impl<S> FileBufferingStream<S> {
         }
     }
 
-    fn write_to_buffer(&mut self, bytes: &Bytes) -> Result<(), BufferingError> {
+    // Absorbs `bytes` into the buffer and returns it unchanged so the caller
+    // can re-emit it straight away - once file-backed, the bytes are only
+    // staged here; `Buffer::File`'s `FileOp` flushes them to disk in the
+    // background via `maybe_flush`/`flush_remaining`.
+    fn write_to_buffer(&mut self, bytes: &Bytes) -> Result<Bytes, BufferingError> {
         match self.buffer {
             Buffer::Memory(ref mut memory) => {
-                if self.threshold < memory.len() + bytes.len() {
-                    let mut path = self.tmp_dir.to_path_buf();
+                if self.options.threshold < memory.len() + bytes.len() {
+                    let mut path = self.options.tmp_dir.to_path_buf();
                     path.push(Uuid::new_v4().to_simple().to_string());
 
-                    let mut file = OpenOptions::new()
-                        .write(true)
-                        .read(true)
-                        .create_new(true)
-                        .open(&path)?;
+                    let content_addressed = self.options.content_addressed;
+                    let prefix = std::mem::replace(memory, BytesMut::new()).freeze();
+                    let emit = bytes.clone();
 
-                    file.write_all(&memory[..])?;
-                    file.write_all(bytes)?;
+                    self.buffer_size += emit.len();
+                    let mut fb = FileBuffer::new(path, self.options.write_buf_size, content_addressed);
+                    fb.stage(prefix);
+                    fb.stage(emit.clone());
+                    fb.maybe_flush();
 
-                    self.buffer = Buffer::File(path, file);
+                    self.buffer = Buffer::File(Box::new(fb));
+
+                    Ok(emit)
                 } else {
-                    memory.extend_from_slice(bytes)
+                    memory.extend_from_slice(bytes);
+                    self.buffer_size += bytes.len();
+                    Ok(bytes.clone())
                 }
             }
-            Buffer::File(_, ref mut file) => {
-                file.write_all(bytes)?;
+            Buffer::File(ref mut fb) => {
+                fb.stage(bytes.clone());
+                fb.maybe_flush();
+                self.buffer_size += bytes.len();
+                Ok(bytes.clone())
             }
         }
-
-        self.buffer_size += bytes.len();
-
-        Ok(())
     }
 
-    fn read_from_buffer(&mut self) -> Result<Bytes, BufferingError> {
-        let chunk_size = self.produce_chunk_size;
+    // Returns `Ok(Some(bytes))` when the next chunk was produced synchronously
+    // (memory-backed, or sliced from a cached mapping), `Ok(None)` when an async read is in flight and the
+    // caller should keep polling `Buffer::File`'s `FileOp`, and an empty
+    // `Bytes` once the buffer is exhausted.
+    fn read_from_buffer(&mut self) -> Result<Option<Bytes>, BufferingError> {
+        let chunk_size = self.options.produce_chunk_size;
         let buffer_size = self.buffer_size;
         let current_index = self.produce_index;
 
         if buffer_size <= current_index {
             self.produce_index = 0;
-            return Ok(Bytes::new());
+            return Ok(Some(Bytes::new()));
         }
 
-        let bytes = match self.buffer {
+        match self.buffer {
             Buffer::Memory(ref memory) => {
-                let bytes = {
-                    if buffer_size <= current_index + chunk_size {
-                        self.produce_index = buffer_size;
-                        let start = current_index as usize;
-                        Bytes::copy_from_slice(&memory[start..])
-                    } else {
-                        self.produce_index += chunk_size;
-                        let start = current_index as usize;
-                        let end = (current_index + chunk_size) as usize;
-                        Bytes::copy_from_slice(&memory[start..end])
-                    }
+                let bytes = if buffer_size <= current_index + chunk_size {
+                    self.produce_index = buffer_size;
+                    Bytes::copy_from_slice(&memory[current_index..])
+                } else {
+                    self.produce_index += chunk_size;
+                    Bytes::copy_from_slice(&memory[current_index..current_index + chunk_size])
                 };
 
-                bytes
+                Ok(Some(bytes))
             }
-            Buffer::File(_, ref mut file) => {
-                if current_index == 0 {
-                    file.seek(SeekFrom::Start(0))?;
-                    file.flush()?;
+            Buffer::File(ref mut fb) => {
+                fb.check_failed()?;
+
+                let seek_to_start = current_index == 0;
+
+                if self.options.mmap_reads && seek_to_start && fb.mmap.is_none() {
+                    if let Some(file) = fb.file.as_ref() {
+                        if let Ok(mapped) = mmap_file(file) {
+                            // The filesystem can briefly report a shorter
+                            // length than what was just written (observed on
+                            // some backends even right after the write future
+                            // resolves) - an unusable mapping here must not
+                            // be cached, or every later read on this body
+                            // would slice out of bounds. Leaving `fb.mmap`
+                            // unset falls back to the normal file-read path
+                            // below for this produce step, and tries mmap
+                            // again on this body's next replay pass.
+                            if mapped.len() >= fb.body_offset as usize + buffer_size {
+                                fb.mmap = Some(mapped);
+                            }
+                        }
+                    }
                 }
 
-                let mut bytes = {
-                    if buffer_size <= current_index + chunk_size {
+                if let Some(ref mapped) = fb.mmap {
+                    let body_offset = fb.body_offset as usize;
+                    let end = if buffer_size <= current_index + chunk_size {
                         self.produce_index = buffer_size;
-                        vec![0u8; buffer_size - current_index]
+                        buffer_size
                     } else {
                         self.produce_index += chunk_size;
-                        vec![0u8; chunk_size]
-                    }
-                };
+                        current_index + chunk_size
+                    };
 
-                file.read_exact(bytes.as_mut_slice())?;
+                    return Ok(Some(
+                        mapped.slice(body_offset + current_index..body_offset + end),
+                    ));
+                }
 
-                bytes.into()
-            }
-        };
+                let len = if buffer_size <= current_index + chunk_size {
+                    self.produce_index = buffer_size;
+                    buffer_size - current_index
+                } else {
+                    self.produce_index += chunk_size;
+                    chunk_size
+                };
 
-        Ok(bytes)
+                fb.start_read(len, seek_to_start);
+                Ok(None)
+            }
+        }
     }
 }
 
@@ -263,46 +969,492 @@ where
     {
         let this = self.get_mut();
 
-        match this.inner_eof {
-            false => {
-                let op = ready!(this.inner.poll_next_unpin(cx));
-                match op {
-                    Some(ref r) => {
-                        if let Ok(ref o) = r {
-                            if let Some(limit) = this.buffer_limit {
+        loop {
+            if let Buffer::File(ref mut fb) = this.buffer {
+                if let Some(poll) = fb.poll_op(cx) {
+                    match poll {
+                        // A background write flush is still draining. It
+                        // carries no bytes the caller is waiting on, so only
+                        // block on it once the inner stream is done - until
+                        // then, keep pulling (and staging) more input.
+                        Poll::Pending if !this.inner_eof => {}
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e.into()))),
+                        Poll::Ready(Ok(FileOpOutcome::Flushed)) => {
+                            if this.inner_eof {
+                                // Chunks staged while the last flush was in
+                                // flight go out now; loop back so `poll_op`
+                                // drains that flush before any replay read.
+                                fb.flush_remaining();
+                                continue;
+                            } else {
+                                fb.maybe_flush();
+                            }
+                        }
+                        Poll::Ready(Ok(FileOpOutcome::Read(bytes))) => {
+                            return Poll::Ready(Some(Ok(bytes)));
+                        }
+                    }
+                }
+            }
+
+            if this.inner_eof {
+                if let Buffer::File(ref mut fb) = this.buffer {
+                    // `flush_remaining` above may have just kicked off one
+                    // more background write; let the top-of-loop `poll_op`
+                    // drain it before touching `fb.file` here.
+                    if fb.content_addressed && !fb.finalized && matches!(fb.op, FileOp::Idle) {
+                        if fb.finalize_op.is_none() {
+                            fb.check_failed()?;
+                            fb.start_finalize(&this.options.tmp_dir, this.buffer_size as u64);
+                        }
+                        ready!(fb.poll_finalize(cx))?;
+                        continue;
+                    }
+                }
+            }
+
+            match this.inner_eof {
+                false => {
+                    let op = ready!(this.inner.poll_next_unpin(cx));
+                    match op {
+                        Some(Ok(ref o)) => {
+                            if let Some(limit) = this.options.buffer_limit {
                                 if this.buffer_size + o.len() > limit {
                                     return Poll::Ready(Some(Err(BufferingError::Overflow.into())));
                                 }
                             }
 
-                            this.write_to_buffer(o)?;
+                            let bytes = this.write_to_buffer(o)?;
+                            return Poll::Ready(Some(Ok(bytes)));
+                        }
+                        Some(Err(e)) => return Poll::Ready(Some(Err(e.into()))),
+                        None => {
+                            this.inner_eof = true;
+                            if let Buffer::File(ref mut fb) = this.buffer {
+                                fb.flush_remaining();
+                            }
+                            // End this pass-through read here - replay only
+                            // starts on a later, separate `poll_next` call.
+                            return Poll::Ready(None);
                         }
                     }
-                    None => {
-                        this.inner_eof = true;
+                }
+                true => match this.read_from_buffer()? {
+                    Some(bytes) if bytes.is_empty() => return Poll::Ready(None),
+                    Some(bytes) => return Poll::Ready(Some(Ok(bytes))),
+                    None => continue,
+                },
+            }
+        }
+    }
+}
+
+// State shared by a `SharedFileBuffering` and every `BufferReader` it hands
+// out. Guarded by one lock since `inner` may only ever be polled by a single
+// caller at a time; whichever reader is first to catch up to the live edge
+// takes the lock, pulls the next chunk, and wakes the others.
+struct SharedCore<S> {
+    inner: S,
+    buffer: Buffer,
+    buffer_size: usize,
+    inner_eof: bool,
+    // Set once `inner` (or a flush driving the file-backed buffer) has
+    // errored out for whichever reader was acting as the puller at the
+    // time. `inner` isn't guaranteed to be fused, so nobody polls it again
+    // after that - every reader past this point (including ones already
+    // parked in `wakers`) gets this terminal error instead.
+    failed: bool,
+}
+
+// A multi-consumer handle produced by `FileBufferingStreamWrapper::wrap_shared`.
+// There is no dedicated driver task pulling `inner` in the background, so any
+// `BufferReader` that catches up to the live edge becomes the one that pulls
+// the next chunk on everyone's behalf, appends it to the shared buffer, and
+// wakes the rest.
+pub struct SharedFileBuffering<S> {
+    options: BufferingOptions,
+
+    core: Mutex<SharedCore<S>>,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl<S> Drop for SharedFileBuffering<S> {
+    fn drop(&mut self) {
+        schedule_buffer_cleanup(&self.core.get_mut().unwrap().buffer);
+    }
+}
+
+impl<S> SharedFileBuffering<S> {
+    // Hands out a fresh, independent replay cursor over the same buffered
+    // body. Readers can be created and polled at any time, including
+    // concurrently with one another and before the inner stream has
+    // finished - a reader that's caught up to what's been buffered so far
+    // just parks until more arrives.
+    pub fn reader(self: &Arc<Self>) -> BufferReader<S> {
+        BufferReader {
+            shared: Arc::clone(self),
+            produce_index: 0,
+            file: None,
+            read_op: None,
+        }
+    }
+
+    fn park(&self, cx: &mut Context<'_>) {
+        self.wakers.lock().unwrap().push(cx.waker().clone());
+    }
+
+    fn wake_all(&self) {
+        for waker in self.wakers.lock().unwrap().drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+impl<S, E> SharedFileBuffering<S>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+{
+    // Absorbs one chunk pulled from `inner` into the shared buffer, mirroring
+    // `FileBufferingStream::write_to_buffer` but for the shared core - there
+    // is no caller waiting on the bytes back, since every reader (including
+    // whichever one triggered this pull) re-reads them from the buffer.
+    fn ingest(&self, core: &mut SharedCore<S>, bytes: &Bytes) {
+        match core.buffer {
+            Buffer::Memory(ref mut memory) => {
+                if self.options.threshold < memory.len() + bytes.len() {
+                    let mut path = self.options.tmp_dir.to_path_buf();
+                    path.push(Uuid::new_v4().to_simple().to_string());
+
+                    let prefix = std::mem::replace(memory, BytesMut::new()).freeze();
+
+                    let mut fb =
+                        FileBuffer::new(path, self.options.write_buf_size, self.options.content_addressed);
+                    fb.stage(prefix);
+                    fb.stage(bytes.clone());
+                    fb.maybe_flush();
+
+                    core.buffer = Buffer::File(Box::new(fb));
+                } else {
+                    memory.extend_from_slice(bytes);
+                }
+            }
+            Buffer::File(ref mut fb) => {
+                fb.stage(bytes.clone());
+                fb.maybe_flush();
+            }
+        }
+
+        core.buffer_size += bytes.len();
+    }
+}
+
+// An independent, reusable cursor over a `SharedFileBuffering`'s buffer.
+// Several of these can be polled concurrently (e.g. the request handler and
+// an audit task), each seeing the same bytes in the same order without
+// waiting on one another once the bytes they need are already buffered.
+pub struct BufferReader<S> {
+    shared: Arc<SharedFileBuffering<S>>,
+    produce_index: usize,
+    // This reader's own file handle and in-flight read, kept separate from
+    // the writer's `FileBuffer::file`/`op` so replaying never contends with
+    // whatever is still being flushed to disk.
+    file: Option<BackendFile>,
+    read_op: Option<ReadFuture>,
+}
+
+impl<S, E> BufferReader<S>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+{
+    fn generic_poll_next<I>(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes, I>>>
+    where
+        E: Into<I>,
+        I: From<BufferingError>,
+    {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(fut) = this.read_op.as_mut() {
+                return match fut.as_mut().poll(cx) {
+                    Poll::Pending => Poll::Pending,
+                    Poll::Ready(Err(e)) => {
+                        this.read_op = None;
+                        Poll::Ready(Some(Err(BufferingError::from(e).into())))
+                    }
+                    Poll::Ready(Ok((file, data))) => {
+                        this.file = Some(file);
+                        this.read_op = None;
+                        Poll::Ready(Some(Ok(Bytes::from(data))))
                     }
                 };
+            }
+
+            let mut core = this.shared.core.lock().unwrap();
+
+            if this.produce_index >= core.buffer_size {
+                if core.failed {
+                    return Poll::Ready(Some(Err(BufferingError::Aborted.into())));
+                }
+
+                if core.inner_eof {
+                    return Poll::Ready(None);
+                }
 
-                Poll::Ready(op.map(|res| res.map_err(Into::into)))
+                return match Pin::new(&mut core.inner).poll_next(cx) {
+                    Poll::Pending => {
+                        this.shared.park(cx);
+                        Poll::Pending
+                    }
+                    Poll::Ready(Some(Err(e))) => {
+                        core.failed = true;
+                        drop(core);
+                        this.shared.wake_all();
+                        Poll::Ready(Some(Err(e.into())))
+                    }
+                    Poll::Ready(Some(Ok(bytes))) => {
+                        if let Some(limit) = this.shared.options.buffer_limit {
+                            if core.buffer_size + bytes.len() > limit {
+                                core.failed = true;
+                                drop(core);
+                                this.shared.wake_all();
+                                return Poll::Ready(Some(Err(BufferingError::Overflow.into())));
+                            }
+                        }
+
+                        this.shared.ingest(&mut core, &bytes);
+                        drop(core);
+                        this.shared.wake_all();
+                        continue;
+                    }
+                    Poll::Ready(None) => {
+                        core.inner_eof = true;
+                        if let Buffer::File(ref mut fb) = core.buffer {
+                            fb.flush_remaining();
+                        }
+                        drop(core);
+                        this.shared.wake_all();
+                        continue;
+                    }
+                };
             }
-            true => {
-                let bytes = this.read_from_buffer()?;
-                if bytes.len() == 0 {
-                    Poll::Ready(None)
-                } else {
-                    Poll::Ready(Some(Ok(bytes)))
+
+            // There's at least one buffered byte this reader hasn't seen yet,
+            // but for a file-backed buffer it may still be in flight rather
+            // than durable - drive that flush (or the final one at EOF) to
+            // completion before reading it back.
+            let available = match core.buffer {
+                Buffer::Memory(_) => core.buffer_size,
+                Buffer::File(ref fb) => fb.durable_len as usize,
+            };
+
+            if this.produce_index >= available {
+                if core.failed {
+                    return Poll::Ready(Some(Err(BufferingError::Aborted.into())));
+                }
+
+                let inner_eof = core.inner_eof;
+                let fb = match core.buffer {
+                    Buffer::File(ref mut fb) => fb,
+                    Buffer::Memory(_) => unreachable!("memory buffers are always fully durable"),
+                };
+
+                return match fb.poll_op(cx) {
+                    Some(Poll::Pending) => {
+                        this.shared.park(cx);
+                        Poll::Pending
+                    }
+                    Some(Poll::Ready(Err(e))) => {
+                        core.failed = true;
+                        drop(core);
+                        this.shared.wake_all();
+                        Poll::Ready(Some(Err(e.into())))
+                    }
+                    Some(Poll::Ready(Ok(FileOpOutcome::Flushed))) => {
+                        if inner_eof {
+                            fb.flush_remaining();
+                        }
+                        drop(core);
+                        this.shared.wake_all();
+                        continue;
+                    }
+                    Some(Poll::Ready(Ok(FileOpOutcome::Read(_)))) => {
+                        // The writer's `FileBuffer` only ever flushes on
+                        // behalf of readers, never reads - unreachable.
+                        continue;
+                    }
+                    None => {
+                        // Nothing flushing yet, and the bytes we want aren't
+                        // durable either. There's no in-flight I/O left to
+                        // wake us - parking here would hang forever if this
+                        // is the only reader, since nothing else is driving
+                        // `inner`. Pull from it ourselves instead, exactly as
+                        // we would if we'd already consumed every buffered
+                        // byte; that either stages enough to cross
+                        // `write_buf_size` (starting a flush) or reaches EOF
+                        // (forcing one via `flush_remaining`).
+                        if inner_eof {
+                            // Unreachable in practice: hitting EOF always
+                            // flushes whatever was still staged, so `op`
+                            // wouldn't be idle here. Park defensively rather
+                            // than spin if that invariant ever breaks.
+                            this.shared.park(cx);
+                            return Poll::Pending;
+                        }
+
+                        return match Pin::new(&mut core.inner).poll_next(cx) {
+                            Poll::Pending => {
+                                this.shared.park(cx);
+                                Poll::Pending
+                            }
+                            Poll::Ready(Some(Err(e))) => {
+                                core.failed = true;
+                                drop(core);
+                                this.shared.wake_all();
+                                Poll::Ready(Some(Err(e.into())))
+                            }
+                            Poll::Ready(Some(Ok(bytes))) => {
+                                if let Some(limit) = this.shared.options.buffer_limit {
+                                    if core.buffer_size + bytes.len() > limit {
+                                        core.failed = true;
+                                        drop(core);
+                                        this.shared.wake_all();
+                                        return Poll::Ready(Some(Err(BufferingError::Overflow.into())));
+                                    }
+                                }
+
+                                this.shared.ingest(&mut core, &bytes);
+                                drop(core);
+                                this.shared.wake_all();
+                                continue;
+                            }
+                            Poll::Ready(None) => {
+                                core.inner_eof = true;
+                                if let Buffer::File(ref mut fb) = core.buffer {
+                                    fb.flush_remaining();
+                                }
+                                drop(core);
+                                this.shared.wake_all();
+                                continue;
+                            }
+                        };
+                    }
+                };
+            }
+
+            let chunk_size = this.shared.options.produce_chunk_size;
+            let current_index = this.produce_index;
+            let inner_eof = core.inner_eof;
+            let buffer_size = core.buffer_size;
+
+            match core.buffer {
+                Buffer::Memory(ref memory) => {
+                    let end = (current_index + chunk_size).min(buffer_size);
+                    this.produce_index = end;
+                    return Poll::Ready(Some(Ok(Bytes::copy_from_slice(&memory[current_index..end]))));
+                }
+                Buffer::File(ref mut fb) => {
+                    let body_offset = fb.body_offset as usize;
+
+                    if this.shared.options.mmap_reads
+                        && inner_eof
+                        && fb.durable_len as usize == buffer_size
+                    {
+                        if fb.mmap.is_none() {
+                            if let Some(file) = fb.file.as_ref() {
+                                if let Ok(mapped) = mmap_file(file) {
+                                    // The filesystem can briefly report a
+                                    // shorter length than what was just
+                                    // written (observed on some backends even
+                                    // right after the write future resolves)
+                                    // - an unusable mapping here must not be
+                                    // cached, or every later read on this
+                                    // body would slice out of bounds. Leaving
+                                    // `fb.mmap` unset falls back to the
+                                    // normal file-read path below for this
+                                    // produce step, and tries mmap again on
+                                    // the next reader's call.
+                                    if mapped.len() >= body_offset + buffer_size {
+                                        fb.mmap = Some(mapped);
+                                    }
+                                }
+                            }
+                        }
+
+                        if let Some(ref mapped) = fb.mmap {
+                            let end = (current_index + chunk_size).min(buffer_size);
+                            this.produce_index = end;
+                            return Poll::Ready(Some(Ok(mapped
+                                .slice(body_offset + current_index..body_offset + end))));
+                        }
+                    }
+
+                    let end = (current_index + chunk_size).min(available);
+                    let len = end - current_index;
+                    let path = fb.path.clone();
+                    let seek_to = body_offset as u64 + current_index as u64;
+                    let mut file = this.file.take();
+
+                    this.produce_index = end;
+                    this.read_op = Some(
+                        async move {
+                            let mut file = match file.take() {
+                                Some(file) => file,
+                                None => open_rw_existing(&path).await?,
+                            };
+                            backend_seek(&mut file, seek_to).await?;
+                            let data = backend_read_exact(&mut file, len).await?;
+                            Ok((file, data))
+                        }
+                        .boxed_local(),
+                    );
+                    continue;
                 }
             }
         }
     }
 }
 
+impl<S> Stream for BufferReader<S>
+where
+    S: Stream<Item = Result<Bytes, actix_web::error::PayloadError>> + Unpin,
+{
+    type Item = Result<Bytes, actix_web::error::PayloadError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.generic_poll_next(cx)
+    }
+}
+
 #[derive(Debug)]
-enum BufferingError {
+pub enum BufferingError {
     Overflow,
     Io(std::io::Error),
+    // A wrapper was configured in a way `wrap`/`wrap_shared` can't honor,
+    // e.g. `content_addressed(true)` together with `wrap_shared`.
+    UnsupportedCombination(&'static str),
+    // A `BufferReader` asked for bytes past the point where some other
+    // reader of the same `SharedFileBuffering` already hit a terminal
+    // error. The original error (an I/O failure, or `Overflow`) was
+    // delivered to whichever reader observed it first; this one just
+    // knows the shared body is dead and isn't going to produce anything
+    // else.
+    Aborted,
 }
 
+impl std::fmt::Display for BufferingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Overflow => write!(f, "buffer limit exceeded"),
+            Self::Io(e) => write!(f, "{}", e),
+            Self::UnsupportedCombination(msg) => write!(f, "{}", msg),
+            Self::Aborted => write!(f, "shared buffer aborted after an earlier reader's error"),
+        }
+    }
+}
+
+impl std::error::Error for BufferingError {}
+
 impl From<std::io::Error> for BufferingError {
     fn from(e: std::io::Error) -> Self {
         Self::Io(e)
@@ -313,7 +1465,7 @@ impl<S, E> MessageBody for FileBufferingStream<S>
 where
     S: Stream<Item = Result<Bytes, E>> + Unpin,
     E: Into<actix_web::Error>,
-{    
+{
     fn size(&self) -> BodySize {
         match self.inner_eof {
             false => BodySize::Stream,
@@ -352,6 +1504,14 @@ impl From<BufferingError> for actix_web::error::PayloadError {
         match e {
             BufferingError::Overflow => actix_web::error::PayloadError::Overflow,
             BufferingError::Io(io) => io.into(),
+            // Never produced by the polling path - `wrap_shared` rejects the
+            // offending configuration before a stream is ever created.
+            BufferingError::UnsupportedCombination(msg) => {
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, msg).into()
+            }
+            BufferingError::Aborted => {
+                std::io::Error::other(e.to_string()).into()
+            }
         }
     }
 }
@@ -361,6 +1521,417 @@ impl From<BufferingError> for actix_web::Error {
         match e {
             BufferingError::Overflow => actix_web::error::PayloadError::Overflow.into(),
             BufferingError::Io(io) => io.into(),
+            BufferingError::UnsupportedCombination(msg) => {
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, msg).into()
+            }
+            BufferingError::Aborted => {
+                std::io::Error::other(e.to_string()).into()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::error::PayloadError;
+    use futures::stream;
+
+    async fn collect<S>(mut s: S) -> Bytes
+    where
+        S: Stream<Item = Result<Bytes, PayloadError>> + Unpin,
+    {
+        let mut out = BytesMut::new();
+        while let Some(chunk) = s.next().await {
+            out.extend_from_slice(&chunk.unwrap());
+        }
+        out.freeze()
+    }
+
+    #[tokio::test]
+    async fn replays_memory_backed_body_after_eof() {
+        let inner = stream::iter(vec![
+            Ok::<_, PayloadError>(Bytes::from_static(b"hello ")),
+            Ok(Bytes::from_static(b"world")),
+        ]);
+        let mut body = FileBufferingStreamWrapper::new().wrap(inner);
+
+        let first = collect(&mut body).await;
+        assert_eq!(first, Bytes::from_static(b"hello world"));
+
+        let second = collect(&mut body).await;
+        assert_eq!(second, Bytes::from_static(b"hello world"));
+    }
+
+    #[tokio::test]
+    async fn spills_past_threshold_and_replays_the_file_backed_body() {
+        let chunk = Bytes::from(vec![b'x'; 16]);
+        let inner = stream::iter(vec![
+            Ok::<_, PayloadError>(chunk.clone()),
+            Ok(chunk.clone()),
+        ]);
+        let mut body = FileBufferingStreamWrapper::new()
+            .threshold(20)
+            .wrap(inner);
+
+        let first = collect(&mut body).await;
+        assert_eq!(first.len(), 32);
+
+        let second = collect(&mut body).await;
+        assert_eq!(second, first);
+    }
+
+    #[tokio::test]
+    async fn two_readers_poll_a_shared_buffer_concurrently() {
+        let inner = stream::iter(vec![
+            Ok::<_, PayloadError>(Bytes::from_static(b"a")),
+            Ok(Bytes::from_static(b"b")),
+            Ok(Bytes::from_static(b"c")),
+        ]);
+        let shared = FileBufferingStreamWrapper::new().wrap_shared(inner).unwrap();
+
+        let reader_a = shared.reader();
+        let reader_b = shared.reader();
+
+        let (a, b) = tokio::join!(collect(reader_a), collect(reader_b));
+        assert_eq!(a, Bytes::from_static(b"abc"));
+        assert_eq!(b, Bytes::from_static(b"abc"));
+    }
+
+    #[tokio::test]
+    async fn sole_reader_over_a_file_backed_buffer_does_not_deadlock() {
+        // A single `BufferReader` with nobody else polling the shared core:
+        // once every staged-but-not-yet-durable chunk sits below
+        // `write_buf_size`, there's no flush in flight and no second reader
+        // to drive `inner` on this reader's behalf. It must keep pulling
+        // `inner` itself instead of parking with nothing left to wake it.
+        let chunk = Bytes::from(vec![b'x'; 50]);
+        let inner = stream::iter((0..50).map(move |_| Ok::<_, PayloadError>(chunk.clone())));
+        let shared = FileBufferingStreamWrapper::new()
+            .threshold(100)
+            .write_buf_size(1024 * 1024)
+            .wrap_shared(inner)
+            .unwrap();
+
+        let reader = shared.reader();
+        let collected = tokio::time::timeout(std::time::Duration::from_secs(5), collect(reader))
+            .await
+            .expect("sole reader over a file-backed buffer must not hang");
+        assert_eq!(collected.len(), 50 * 50);
+    }
+
+    #[tokio::test]
+    async fn a_pullers_error_wakes_and_errors_every_parked_reader() {
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+        use std::task::Wake;
+
+        struct FlagWaker(AtomicBool);
+        impl Wake for FlagWaker {
+            fn wake(self: Arc<Self>) {
+                self.wake_by_ref();
+            }
+            fn wake_by_ref(self: &Arc<Self>) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        // Pending on the first poll (to let `reader_b` park), then an error -
+        // mirrors a client aborting the upload mid-stream.
+        let calls = AtomicUsize::new(0);
+        let inner = stream::poll_fn(move |cx| {
+            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            } else {
+                Poll::Ready(Some(Err::<Bytes, PayloadError>(PayloadError::Incomplete(None))))
+            }
+        });
+        let shared = FileBufferingStreamWrapper::new().wrap_shared(inner).unwrap();
+
+        let mut reader_a = shared.reader();
+        let mut reader_b = shared.reader();
+
+        let b_waker = Arc::new(FlagWaker(AtomicBool::new(false)));
+        let b_raw_waker = Waker::from(Arc::clone(&b_waker));
+        let mut b_cx = Context::from_waker(&b_raw_waker);
+        assert!(matches!(
+            Pin::new(&mut reader_b).poll_next(&mut b_cx),
+            Poll::Pending
+        ));
+
+        let a_waker = futures::task::noop_waker();
+        let mut a_cx = Context::from_waker(&a_waker);
+        assert!(matches!(
+            Pin::new(&mut reader_a).poll_next(&mut a_cx),
+            Poll::Ready(Some(Err(_)))
+        ));
+
+        assert!(
+            b_waker.0.load(Ordering::SeqCst),
+            "parked reader must be woken once the puller hits a terminal error"
+        );
+        assert!(
+            matches!(
+                Pin::new(&mut reader_b).poll_next(&mut b_cx),
+                Poll::Ready(Some(Err(_)))
+            ),
+            "parked reader must surface the terminal error instead of hanging forever"
+        );
+    }
+
+    fn file_path<S>(stream: &FileBufferingStream<S>) -> PathBuf {
+        match &stream.buffer {
+            Buffer::File(fb) => fb.path.clone(),
+            Buffer::Memory(_) => panic!("expected a file-backed buffer"),
+        }
+    }
+
+    #[tokio::test]
+    async fn dropping_before_any_flush_does_not_try_to_remove_a_never_created_file() {
+        // A `write_buf_size` larger than the first chunk means `maybe_flush`
+        // never actually starts a flush, so the spilled path is never opened
+        // on disk - dropping the stream here (e.g. a connection dropped
+        // mid-upload) must not try to unlink it.
+        let chunk = Bytes::from(vec![b'd'; 32]);
+        let mut body = FileBufferingStreamWrapper::new()
+            .threshold(8)
+            .write_buf_size(1024 * 1024)
+            .wrap(stream::iter(vec![Ok::<_, PayloadError>(chunk.clone())]));
+
+        assert_eq!(body.next().await.unwrap().unwrap(), chunk);
+
+        let path = file_path(&body);
+        // Plant a file at the path cleanup would target if it incorrectly
+        // tried to remove it - a real bug here would delete it.
+        tokio::fs::write(&path, b"sentinel").await.unwrap();
+
+        drop(body);
+        tokio::task::yield_now().await;
+
+        assert_eq!(tokio::fs::read(&path).await.unwrap(), b"sentinel");
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn content_addressed_dedups_identical_bodies_and_refcounts_blobs() {
+        // Content-addressing keys the blob off the bytes themselves, and the
+        // store outlives any one test process - a fixed payload would hash to
+        // whatever a previous run already left on disk. Mixing in a fresh
+        // uuid keeps this run's digest unique.
+        let chunk = Bytes::from(format!("x-{}", Uuid::new_v4()).into_bytes());
+        let wrapper = FileBufferingStreamWrapper::new()
+            .threshold(8)
+            .content_addressed(true);
+
+        let mut body_a = wrapper.wrap(stream::iter(vec![Ok::<_, PayloadError>(chunk.clone())]));
+        let mut body_b = wrapper.wrap(stream::iter(vec![Ok::<_, PayloadError>(chunk.clone())]));
+
+        assert_eq!(collect(&mut body_a).await, chunk);
+        assert_eq!(collect(&mut body_b).await, chunk);
+
+        // The second pass over each body is what drives `start_finalize`.
+        assert_eq!(collect(&mut body_a).await, chunk);
+        assert_eq!(collect(&mut body_b).await, chunk);
+
+        let path_a = file_path(&body_a);
+        let path_b = file_path(&body_b);
+        assert_eq!(path_a, path_b, "identical bodies should share one blob");
+        assert_eq!(
+            content_store::refs().lock().unwrap().get(&path_a).copied(),
+            Some(2)
+        );
+
+        assert!(verify_content_addressed_blob(&path_a).await.unwrap());
+
+        // Dropping one reference must keep the blob alive for the other.
+        drop(body_a);
+        assert_eq!(
+            content_store::refs().lock().unwrap().get(&path_b).copied(),
+            Some(1)
+        );
+
+        drop(body_b);
+        assert!(content_store::refs().lock().unwrap().get(&path_b).is_none());
+    }
+
+    #[tokio::test]
+    async fn verify_content_addressed_blob_detects_corruption() {
+        // Same reasoning as above - and especially here, since this test
+        // intentionally corrupts the blob it writes: a fixed payload would
+        // let a later run collide with (and immediately "discover") damage
+        // this test itself left behind on a previous pass.
+        let chunk = Bytes::from(format!("y-{}", Uuid::new_v4()).into_bytes());
+        let mut body = FileBufferingStreamWrapper::new()
+            .threshold(8)
+            .content_addressed(true)
+            .wrap(stream::iter(vec![Ok::<_, PayloadError>(chunk.clone())]));
+
+        assert_eq!(collect(&mut body).await, chunk);
+        assert_eq!(collect(&mut body).await, chunk);
+
+        let path = file_path(&body);
+        assert!(verify_content_addressed_blob(&path).await.unwrap());
+
+        let mut contents = tokio::fs::read(&path).await.unwrap();
+        let last = contents.len() - 1;
+        contents[last] ^= 0xff;
+        tokio::fs::write(&path, &contents).await.unwrap();
+
+        assert!(!verify_content_addressed_blob(&path).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn replay_errors_again_after_an_io_error_instead_of_panicking() {
+        // A prior I/O error drops `FileBuffer::file` along with the future
+        // that owned it. Re-polling after that (the `Stream` contract
+        // doesn't forbid it, and it's what a caller's own retry loop might
+        // do) must return an error again, not panic on a missing file
+        // handle. A small `produce_chunk_size` spreads the replay over
+        // several reads so the short read lands mid-pass, with more of the
+        // body still to come, rather than conveniently as the last chunk.
+        let chunk = Bytes::from(vec![b'z'; 64]);
+        let mut body = FileBufferingStreamWrapper::new()
+            .threshold(8)
+            .produce_chunk_size(8)
+            .wrap(stream::iter(vec![Ok::<_, PayloadError>(chunk.clone())]));
+
+        assert_eq!(collect(&mut body).await, chunk);
+
+        // The first replay read is what actually drives the deferred flush
+        // to completion, so the spilled file only exists on disk once it
+        // returns - only then is there anything to truncate.
+        assert_eq!(body.next().await.unwrap().unwrap(), Bytes::from(vec![b'z'; 8]));
+
+        let path = file_path(&body);
+        // Truncate the spilled file out from under the stream, simulating a
+        // short/corrupted read partway through replay.
+        tokio::fs::write(&path, vec![b'z'; 20]).await.unwrap();
+
+        assert_eq!(body.next().await.unwrap().unwrap(), Bytes::from(vec![b'z'; 8]));
+        assert!(matches!(body.next().await, Some(Err(_))));
+        assert!(matches!(body.next().await, Some(Err(_))));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn mmap_reads_serves_correct_bytes_via_the_mmap_path() {
+        let chunk = Bytes::from(vec![b'm'; 64]);
+        let mut body = FileBufferingStreamWrapper::new()
+            .threshold(8)
+            .mmap_reads(true)
+            .wrap(stream::iter(vec![Ok::<_, PayloadError>(chunk.clone())]));
+
+        assert_eq!(collect(&mut body).await, chunk);
+
+        // The replay pass is what lazily establishes the mapping. Every pass
+        // must return the right bytes regardless of whether it went through
+        // the mmap path or its copy fallback; a freshly-written file's
+        // length can occasionally not have settled yet on the first replay,
+        // so give it a few passes to actually land on the mmap path before
+        // asserting it was used.
+        let mut used_mmap = false;
+        for _ in 0..10 {
+            let replayed = collect(&mut body).await;
+            assert_eq!(replayed, chunk);
+
+            match &body.buffer {
+                Buffer::File(fb) => used_mmap = fb.mmap.is_some(),
+                Buffer::Memory(_) => panic!("expected a file-backed buffer"),
+            }
+            if used_mmap {
+                break;
+            }
+        }
+
+        assert!(
+            used_mmap,
+            "expected a replay to go through the mmap path, not just the copy fallback"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn mmap_reads_serves_correct_bytes_via_a_shared_buffer_reader() {
+        // The mmap path is wired up separately for `BufferReader` (shared
+        // readers) and `FileBufferingStream::read_from_buffer` (the
+        // single-consumer path) - exercise it through a `BufferReader` too,
+        // not just `wrap`.
+        let chunk = Bytes::from(vec![b'n'; 64]);
+        let shared = FileBufferingStreamWrapper::new()
+            .threshold(8)
+            .mmap_reads(true)
+            .wrap_shared(stream::iter(vec![Ok::<_, PayloadError>(chunk.clone())]))
+            .unwrap();
+
+        assert_eq!(collect(shared.reader()).await, chunk);
+
+        // Same reasoning as the single-consumer version above: a
+        // freshly-written file's length can occasionally not have settled
+        // yet on the first replay, so give it a few readers' worth of
+        // passes to actually land on the mmap path before asserting it.
+        let mut used_mmap = false;
+        for _ in 0..10 {
+            assert_eq!(collect(shared.reader()).await, chunk);
+
+            match &shared.core.lock().unwrap().buffer {
+                Buffer::File(fb) => used_mmap = fb.mmap.is_some(),
+                Buffer::Memory(_) => panic!("expected a file-backed buffer"),
+            }
+            if used_mmap {
+                break;
+            }
+        }
+
+        assert!(
+            used_mmap,
+            "expected a shared reader's replay to go through the mmap path, not just the copy fallback"
+        );
+    }
+
+    #[tokio::test]
+    async fn write_buf_size_defers_flushes_until_threshold_or_eof() {
+        let inner = stream::iter(vec![
+            Ok::<_, PayloadError>(Bytes::from_static(b"aaaa")),
+            Ok(Bytes::from_static(b"bbbb")),
+            Ok(Bytes::from_static(b"cccc")),
+        ]);
+        let mut body = FileBufferingStreamWrapper::new()
+            .threshold(2)
+            .write_buf_size(1024)
+            .wrap(inner);
+
+        for _ in 0..3 {
+            body.next().await.unwrap().unwrap();
+
+            match &body.buffer {
+                Buffer::File(fb) => {
+                    assert_eq!(
+                        fb.durable_len, 0,
+                        "nothing should hit disk before write_buf_size is crossed or EOF is reached"
+                    );
+                    assert!(
+                        matches!(fb.op, FileOp::Idle),
+                        "staging below write_buf_size must not start a flush"
+                    );
+                }
+                Buffer::Memory(_) => panic!("expected a file-backed buffer"),
+            }
+        }
+
+        // The inner stream has one more chunk to give up: `None`, ending
+        // this pass-through read. Only the next, separate pass replays the
+        // buffer, and that's what forces the deferred flush onto disk.
+        assert!(body.next().await.is_none());
+
+        let replayed = collect(&mut body).await;
+        assert_eq!(replayed, Bytes::from_static(b"aaaabbbbcccc"));
+
+        match &body.buffer {
+            Buffer::File(fb) => assert_eq!(
+                fb.durable_len, 12,
+                "flush_remaining at EOF should have landed everything that was staged"
+            ),
+            Buffer::Memory(_) => panic!("expected a file-backed buffer"),
         }
     }
 }
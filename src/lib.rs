@@ -0,0 +1,25 @@
+//! Request/Response body buffering with support spooled to a temp file on disk
+//!
+//! Use it in actix-web middleware. For example this is used at [actix-web-detached-jws-middleware](https://crates.io/crates/actix-web-detached-jws-middleware)
+//!
+//! ## Known limitations
+//!
+//! Spilled bodies are read and written through plain `tokio::fs`. An
+//! `io-uring` backend was tried as an alternative and pulled again (see the
+//! `BackendFile` comment in `buffering.rs`) because its ops can only run
+//! inside a dedicated `tokio_uring` driver, which doesn't fit the plain
+//! actix-rt/tokio runtime this crate targets without a larger bridging
+//! effort. That's an open follow-up, not a silently dropped requirement -
+//! revisit it if a caller actually needs it.
+//!
+//! This was the primary ask of the request that introduced the async file
+//! backend, so before calling that request done, get explicit sign-off from
+//! whoever filed it that shipping without `io-uring` is acceptable - a doc
+//! comment explaining the reason isn't a substitute for that scope
+//! agreement.
+pub mod buffering;
+
+pub use crate::buffering::{
+    enable_request_buffering, enable_response_buffering, verify_content_addressed_blob,
+    BufferReader, FileBufferingStreamWrapper, SharedFileBuffering,
+};